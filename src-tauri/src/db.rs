@@ -0,0 +1,101 @@
+use crate::{MedicalResponse, SessionInfo};
+use rusqlite::{params, Connection, Result as SqlResult};
+use std::path::Path;
+use std::sync::Mutex;
+
+/// On-device SQLite store for sessions and conversation history, so history survives
+/// app restarts instead of living only in the in-memory maps of `AppState`.
+pub struct Store {
+    conn: Mutex<Connection>,
+}
+
+impl Store {
+    pub fn open(db_path: &Path) -> SqlResult<Self> {
+        let conn = Connection::open(db_path)?;
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS sessions (
+                session_id TEXT PRIMARY KEY,
+                session_type TEXT NOT NULL,
+                created_at TEXT NOT NULL
+            );
+            CREATE TABLE IF NOT EXISTS conversations (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                session_id TEXT NOT NULL,
+                response_json TEXT NOT NULL,
+                timestamp TEXT NOT NULL
+            );
+            CREATE INDEX IF NOT EXISTS idx_conversations_session
+                ON conversations (session_id, timestamp);",
+        )?;
+
+        Ok(Self {
+            conn: Mutex::new(conn),
+        })
+    }
+
+    pub fn insert_session(&self, session: &SessionInfo) -> SqlResult<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT OR REPLACE INTO sessions (session_id, session_type, created_at) VALUES (?1, ?2, ?3)",
+            params![session.session_id, session.session_type, session.created_at],
+        )?;
+        Ok(())
+    }
+
+    pub fn insert_conversation(&self, response: &MedicalResponse) -> SqlResult<()> {
+        let conn = self.conn.lock().unwrap();
+        let response_json = serde_json::to_string(response)
+            .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
+        conn.execute(
+            "INSERT INTO conversations (session_id, response_json, timestamp) VALUES (?1, ?2, ?3)",
+            params![response.session_id, response_json, response.timestamp],
+        )?;
+        Ok(())
+    }
+
+    pub fn session_history(&self, session_id: &str) -> SqlResult<Vec<MedicalResponse>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT response_json FROM conversations WHERE session_id = ?1 ORDER BY timestamp",
+        )?;
+        let rows = stmt.query_map(params![session_id], |row| row.get::<_, String>(0))?;
+
+        let mut history = Vec::new();
+        for row in rows {
+            let response_json = row?;
+            if let Ok(response) = serde_json::from_str(&response_json) {
+                history.push(response);
+            }
+        }
+        Ok(history)
+    }
+
+    pub fn delete_session(&self, session_id: &str) -> SqlResult<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "DELETE FROM conversations WHERE session_id = ?1",
+            params![session_id],
+        )?;
+        conn.execute(
+            "DELETE FROM sessions WHERE session_id = ?1",
+            params![session_id],
+        )?;
+        Ok(())
+    }
+
+    pub fn session_count(&self) -> SqlResult<i64> {
+        let conn = self.conn.lock().unwrap();
+        conn.query_row("SELECT COUNT(*) FROM sessions", [], |row| row.get(0))
+    }
+
+    pub fn conversation_count(&self) -> SqlResult<i64> {
+        let conn = self.conn.lock().unwrap();
+        conn.query_row("SELECT COUNT(*) FROM conversations", [], |row| row.get(0))
+    }
+
+    /// Cheap liveness probe for health checks.
+    pub fn ping(&self) -> SqlResult<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.query_row("SELECT 1", [], |_row| Ok(()))
+    }
+}
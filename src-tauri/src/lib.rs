@@ -1,14 +1,20 @@
+mod db;
 mod medical_ai;
 mod ollama_manager;
+mod rag;
+mod rate_limiter;
+mod semantic_search;
 
+use db::Store;
 use medical_ai::MedicalAI;
-use ollama_manager::{OllamaConfig, OllamaManager};
+use ollama_manager::{ChatOptions, ModelState, OllamaConfig, OllamaManager};
+use semantic_search::ConditionIndex;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
 use std::sync::Arc;
 use std::sync::Mutex;
-use tauri::{Manager, State};
+use tauri::{Emitter, Manager, State};
 use tokio::sync::Mutex as AsyncMutex;
+use tokio::sync::RwLock;
 use uuid::Uuid;
 
 // Data structures for medical assistance
@@ -69,26 +75,80 @@ pub struct AIModelInfo {
     pub default_model: String,
     pub medical_model: String,
     pub ollama_url: String,
+    pub model_state: ModelState,
 }
 
 // Application State
-#[derive(Default)]
 pub struct AppState {
-    pub sessions: Mutex<HashMap<String, SessionInfo>>,
-    pub conversations: Mutex<Vec<MedicalResponse>>,
-    pub medical_ai: Arc<AsyncMutex<Option<MedicalAI>>>,
+    /// `RwLock` rather than a plain mutex so a long-running streaming query (which only
+    /// needs shared access) doesn't block other commands that merely read the AI state,
+    /// such as `get_system_health` or a second concurrent query. Only model/endpoint
+    /// changes need the write half.
+    pub medical_ai: Arc<RwLock<Option<MedicalAI>>>,
+    pub condition_index: Arc<AsyncMutex<Option<ConditionIndex>>>,
+    pub store: Mutex<Option<Store>>,
 }
 
 impl AppState {
     pub fn new() -> Self {
         Self {
-            sessions: Mutex::new(HashMap::new()),
-            conversations: Mutex::new(Vec::new()),
-            medical_ai: Arc::new(AsyncMutex::new(None)),
+            medical_ai: Arc::new(RwLock::new(None)),
+            condition_index: Arc::new(AsyncMutex::new(None)),
+            store: Mutex::new(None),
         }
     }
 }
 
+/// Related-condition matches above this cosine similarity are surfaced to the user.
+const RELATED_CONDITIONS_THRESHOLD: f64 = 0.55;
+const RELATED_CONDITIONS_TOP_K: usize = 5;
+
+/// Per-query sampling/context options accepted by `set_generation_settings` and applied
+/// directly to `OllamaManager`'s chat options (see `ollama_manager::ChatOptions`), so
+/// every query path shares the same values instead of each keeping its own copy. The
+/// medical-disclaimer system prompt is a separate concern, owned solely by
+/// `medical_ai::medical_system_prompt`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GenerationSettings {
+    pub num_ctx: u32,
+    pub temperature: Option<f64>,
+    pub top_p: Option<f64>,
+    pub num_predict: Option<i32>,
+    pub stop: Option<Vec<String>>,
+}
+
+/// Writes a conversation turn through to the SQLite store, logging (rather than failing
+/// the request) if the store isn't available or the write errors out.
+fn persist_conversation(state: &AppState, response: &MedicalResponse) {
+    let store_guard = state.store.lock().unwrap();
+    if let Some(ref store) = *store_guard {
+        if let Err(e) = store.insert_conversation(response) {
+            eprintln!("Failed to persist conversation: {}", e);
+        }
+    }
+}
+
+/// Looks up semantically related conditions for `query`, building the embedding index
+/// lazily on first use. Returns `None` if the AI service isn't initialized or the
+/// embedding model is unavailable so the main response can still succeed without
+/// related conditions.
+async fn find_related_conditions(state: &AppState, query: &str) -> Option<Vec<RelatedCondition>> {
+    let medical_ai_guard = state.medical_ai.read().await;
+    let ollama = medical_ai_guard.as_ref()?.ollama();
+
+    let mut index_guard = state.condition_index.lock().await;
+    if index_guard.is_none() {
+        *index_guard = ConditionIndex::build(ollama).await;
+    }
+
+    let index = index_guard.as_ref()?;
+    let matches = index
+        .search(query, RELATED_CONDITIONS_TOP_K, RELATED_CONDITIONS_THRESHOLD, ollama)
+        .await;
+
+    Some(matches)
+}
+
 // Tauri Commands
 #[tauri::command]
 async fn create_session(
@@ -102,8 +162,12 @@ async fn create_session(
         created_at: chrono::Utc::now().to_rfc3339(),
     };
 
-    let mut sessions = state.sessions.lock().unwrap();
-    sessions.insert(session_id.clone(), session_info.clone());
+    let store_guard = state.store.lock().unwrap();
+    if let Some(ref store) = *store_guard {
+        store
+            .insert_session(&session_info)
+            .map_err(|e| format!("Failed to persist session: {}", e))?;
+    }
 
     Ok(session_info)
 }
@@ -118,7 +182,7 @@ async fn submit_medical_query(
         .unwrap_or_else(|| Uuid::new_v4().to_string());
 
     // Get the medical AI instance
-    let medical_ai_guard = state.medical_ai.lock().await;
+    let medical_ai_guard = state.medical_ai.read().await;
 
     if let Some(ref medical_ai) = *medical_ai_guard {
         // Create a medical query for our AI
@@ -128,9 +192,12 @@ async fn submit_medical_query(
             session_id: session_id.clone(),
         };
 
-        // Process the query with our AI
+        // `process_medical_query` throttles its own Ollama requests internally.
         match medical_ai.process_medical_query(&ai_query).await {
             Ok(ai_response) => {
+                drop(medical_ai_guard); // Release the lock before the embedding lookup
+                let related_conditions = find_related_conditions(&state, &query.query).await;
+
                 let medical_response = MedicalResponse {
                     response: ai_response.response,
                     confidence: ai_response.confidence,
@@ -145,13 +212,11 @@ async fn submit_medical_query(
                         emergency_action: g.emergency_action,
                         follow_up: g.follow_up,
                     }),
-                    related_conditions: None,
+                    related_conditions,
                 };
 
                 // Store the conversation
-                drop(medical_ai_guard); // Release the lock before acquiring another
-                let mut conversations = state.conversations.lock().unwrap();
-                conversations.push(medical_response.clone());
+                persist_conversation(&state, &medical_response);
 
                 Ok(medical_response)
             }
@@ -178,8 +243,7 @@ async fn submit_medical_query(
                 };
 
                 drop(medical_ai_guard);
-                let mut conversations = state.conversations.lock().unwrap();
-                conversations.push(medical_response.clone());
+                persist_conversation(&state, &medical_response);
 
                 Ok(medical_response)
             }
@@ -208,17 +272,80 @@ async fn submit_medical_query(
             related_conditions: None,
         };
 
-        let mut conversations = state.conversations.lock().unwrap();
-        conversations.push(medical_response.clone());
+        persist_conversation(&state, &medical_response);
 
         Ok(medical_response)
     }
 }
 
+/// Streams the reply via `MedicalAI`/`OllamaManager`'s `/api/chat`-based `chat_stream`,
+/// so it shares the exact same system prompt, model selection, sampling options, and
+/// emergency analysis as the non-streaming `submit_medical_query`.
+#[tauri::command]
+async fn submit_medical_query_chat_stream(
+    app_handle: tauri::AppHandle,
+    query: MedicalQuery,
+    state: State<'_, AppState>,
+) -> Result<MedicalResponse, String> {
+    let session_id = query
+        .session_id
+        .clone()
+        .unwrap_or_else(|| Uuid::new_v4().to_string());
+    let event_name = format!("medical-chat-stream-{}", session_id);
+
+    // `read()` rather than `write()`: streaming can take several seconds, and a shared
+    // read lock lets other commands (health checks, a second concurrent query) proceed
+    // instead of queuing up behind this one for the whole duration.
+    let medical_ai_guard = state.medical_ai.read().await;
+    let medical_ai = medical_ai_guard
+        .as_ref()
+        .ok_or_else(|| "AI service is not initialized".to_string())?;
+
+    let ai_query = medical_ai::MedicalQuery {
+        query: query.query.clone(),
+        query_type: query.query_type.clone(),
+        session_id: session_id.clone(),
+    };
+
+    // `process_medical_query_stream` throttles its own Ollama requests internally.
+    let ai_response = medical_ai
+        .process_medical_query_stream(&ai_query, &app_handle, &event_name)
+        .await
+        .map_err(|e| format!("Chat streaming failed: {}", e))?;
+    drop(medical_ai_guard);
+
+    let related_conditions = find_related_conditions(&state, &query.query).await;
+
+    let medical_response = MedicalResponse {
+        response: ai_response.response,
+        confidence: ai_response.confidence,
+        session_id: session_id.clone(),
+        conversation_id: Some(1),
+        query_type: query.query_type.clone(),
+        timestamp: chrono::Utc::now().to_rfc3339(),
+        emergency_detected: Some(ai_response.emergency_detected),
+        medical_guidance: ai_response.medical_guidance.map(|g| MedicalGuidance {
+            severity: g.severity,
+            recommendations: Some(g.recommendations),
+            emergency_action: g.emergency_action,
+            follow_up: g.follow_up,
+        }),
+        related_conditions,
+    };
+
+    persist_conversation(&state, &medical_response);
+
+    app_handle
+        .emit(&event_name, &medical_response)
+        .map_err(|e| format!("Failed to emit final stream event: {}", e))?;
+
+    Ok(medical_response)
+}
+
 #[tauri::command]
 async fn get_system_health(state: State<'_, AppState>) -> Result<SystemHealth, String> {
-    // Check AI service status
-    let medical_ai_guard = state.medical_ai.lock().await;
+    // Check AI service status by actually pinging Ollama's /api/tags.
+    let medical_ai_guard = state.medical_ai.read().await;
     let ai_status = if let Some(ref medical_ai) = *medical_ai_guard {
         match medical_ai.health_check().await {
             Ok(true) => "healthy",
@@ -228,28 +355,104 @@ async fn get_system_health(state: State<'_, AppState>) -> Result<SystemHealth, S
     } else {
         "not_initialized"
     };
+    drop(medical_ai_guard);
+
+    // Check the SQLite store by actually running a query against it.
+    let database_status = {
+        let store_guard = state.store.lock().unwrap();
+        match store_guard.as_ref() {
+            Some(store) => match store.ping() {
+                Ok(()) => "healthy",
+                Err(_) => "error",
+            },
+            None => "not_initialized",
+        }
+    };
+
+    // The database is the core store: if it's down, nothing else matters. Otherwise
+    // any unhealthy component (just the AI service, here) drops us to "degraded".
+    let status = if database_status != "healthy" {
+        "unhealthy"
+    } else if ai_status != "healthy" {
+        "degraded"
+    } else {
+        "healthy"
+    };
 
     Ok(SystemHealth {
-        status: "healthy".to_string(),
-        database: "healthy".to_string(),
+        status: status.to_string(),
+        database: database_status.to_string(),
         ai_service: ai_status.to_string(),
         timestamp: chrono::Utc::now().to_rfc3339(),
     })
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct VersionInfo {
+    pub app_version: String,
+    pub ollama_version: Option<String>,
+    pub active_model: Option<String>,
+    pub session_count: i64,
+    pub conversation_count: i64,
+}
+
+#[tauri::command]
+async fn get_version_info(state: State<'_, AppState>) -> Result<VersionInfo, String> {
+    let medical_ai_guard = state.medical_ai.read().await;
+    let (ollama_version, active_model) = match *medical_ai_guard {
+        Some(ref medical_ai) => (
+            medical_ai.ollama_version().await.ok(),
+            Some(medical_ai.current_model().to_string()),
+        ),
+        None => (None, None),
+    };
+    drop(medical_ai_guard);
+
+    let (session_count, conversation_count) = {
+        let store_guard = state.store.lock().unwrap();
+        match store_guard.as_ref() {
+            Some(store) => (
+                store.session_count().unwrap_or(0),
+                store.conversation_count().unwrap_or(0),
+            ),
+            None => (0, 0),
+        }
+    };
+
+    Ok(VersionInfo {
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        ollama_version,
+        active_model,
+        session_count,
+        conversation_count,
+    })
+}
+
 #[tauri::command]
 async fn get_session_history(
     session_id: String,
     state: State<'_, AppState>,
 ) -> Result<Vec<MedicalResponse>, String> {
-    let conversations = state.conversations.lock().unwrap();
-    let session_conversations: Vec<MedicalResponse> = conversations
-        .iter()
-        .filter(|conv| conv.session_id == session_id)
-        .cloned()
-        .collect();
+    let store_guard = state.store.lock().unwrap();
+    let store = store_guard
+        .as_ref()
+        .ok_or_else(|| "Session store is not initialized".to_string())?;
+
+    store
+        .session_history(&session_id)
+        .map_err(|e| format!("Failed to read session history: {}", e))
+}
 
-    Ok(session_conversations)
+#[tauri::command]
+async fn delete_session(session_id: String, state: State<'_, AppState>) -> Result<(), String> {
+    let store_guard = state.store.lock().unwrap();
+    if let Some(ref store) = *store_guard {
+        store
+            .delete_session(&session_id)
+            .map_err(|e| format!("Failed to delete session: {}", e))?;
+    }
+
+    Ok(())
 }
 
 #[tauri::command]
@@ -259,37 +462,53 @@ async fn initialize_ai_service(app_handle: tauri::AppHandle) -> Result<String, S
 
 async fn start_ai_service_internal(app_handle: tauri::AppHandle) -> Result<String, String> {
     let state = app_handle.state::<AppState>();
-    let mut medical_ai_guard = state.medical_ai.lock().await;
+    let mut medical_ai_guard = state.medical_ai.write().await;
 
     if medical_ai_guard.is_some() {
         return Ok("AI service already initialized".to_string());
     }
 
-    // Create Ollama manager with default config
-    let config = OllamaConfig::default();
+    // Pick up OLLAMA_URL/OLLAMA_API_KEY from the environment so remote/authenticated
+    // endpoints don't require a recompile.
+    let config = OllamaConfig::from_env();
+    let remote_configured = config.remote_configured;
     let ollama_manager = OllamaManager::new(config);
 
-    // Try to start embedded Ollama first
-    match ollama_manager.start_embedded(&app_handle).await {
-        Ok(_) => {
-            println!("Embedded Ollama started successfully");
+    if remote_configured {
+        // A remote host was explicitly configured: skip the embedded-startup attempt
+        // and just verify it's reachable.
+        match ollama_manager.health_check().await {
+            Ok(true) => {
+                println!("Connected to configured remote Ollama");
+            }
+            Ok(false) | Err(_) => {
+                return Err("Configured remote Ollama endpoint is not reachable".to_string());
+            }
         }
-        Err(e) => {
-            println!("Failed to start embedded Ollama, trying external: {}", e);
-            // Try to connect to external Ollama
-            match ollama_manager.health_check().await {
-                Ok(true) => {
-                    println!("Connected to external Ollama");
-                }
-                Ok(false) | Err(_) => {
-                    return Err("No Ollama instance available (embedded or external)".to_string());
+    } else {
+        // Try to start embedded Ollama first
+        match ollama_manager.start_embedded(&app_handle).await {
+            Ok(_) => {
+                println!("Embedded Ollama started successfully");
+            }
+            Err(e) => {
+                println!("Failed to start embedded Ollama, trying external: {}", e);
+                // Try to connect to external Ollama
+                match ollama_manager.health_check().await {
+                    Ok(true) => {
+                        println!("Connected to external Ollama");
+                    }
+                    Ok(false) | Err(_) => {
+                        return Err("No Ollama instance available (embedded or external)".to_string());
+                    }
                 }
             }
         }
     }
 
-    // Ensure the model is ready
-    match ollama_manager.ensure_model("tinyllama:latest").await {
+    // Ensure the model is installed and preloaded into memory so the first user query
+    // doesn't pay for the cold load.
+    match ollama_manager.ensure_model_ready("tinyllama:latest").await {
         Ok(_) => {
             println!("Model ready: tinyllama:latest");
         }
@@ -299,8 +518,12 @@ async fn start_ai_service_internal(app_handle: tauri::AppHandle) -> Result<Strin
         }
     }
 
-    // Create medical AI instance
-    let medical_ai = MedicalAI::new(ollama_manager);
+    // Create medical AI instance, grounding it in the bundled reference corpus when we
+    // have somewhere to persist the embedded chunks.
+    let medical_ai = match app_handle.path().app_data_dir() {
+        Ok(data_dir) => MedicalAI::with_rag(ollama_manager, &data_dir).await,
+        Err(_) => MedicalAI::new(ollama_manager),
+    };
     *medical_ai_guard = Some(medical_ai);
 
     Ok("AI service initialized successfully".to_string())
@@ -308,23 +531,27 @@ async fn start_ai_service_internal(app_handle: tauri::AppHandle) -> Result<Strin
 
 #[tauri::command]
 async fn stop_ai_service(state: State<'_, AppState>) -> Result<String, String> {
-    let mut medical_ai_guard = state.medical_ai.lock().await;
+    let mut medical_ai_guard = state.medical_ai.write().await;
     *medical_ai_guard = None;
     Ok("AI service stopped".to_string())
 }
 
 #[tauri::command]
 async fn get_ai_models(state: State<'_, AppState>) -> Result<AIModelInfo, String> {
-    let medical_ai_guard = state.medical_ai.lock().await;
+    let medical_ai_guard = state.medical_ai.read().await;
 
     if let Some(ref medical_ai) = *medical_ai_guard {
         let available = medical_ai.health_check().await.unwrap_or(false);
+        let models = medical_ai.list_models().await.unwrap_or_default();
+        let active_model = medical_ai.current_model().to_string();
+
         Ok(AIModelInfo {
             available,
-            models: vec!["tinyllama:latest".to_string(), "llama2:latest".to_string()],
-            default_model: "tinyllama:latest".to_string(),
-            medical_model: "tinyllama:latest".to_string(),
-            ollama_url: "http://127.0.0.1:11434".to_string(),
+            models,
+            default_model: active_model.clone(),
+            medical_model: active_model,
+            ollama_url: medical_ai.ollama_url(),
+            model_state: medical_ai.model_state().await,
         })
     } else {
         Ok(AIModelInfo {
@@ -333,10 +560,107 @@ async fn get_ai_models(state: State<'_, AppState>) -> Result<AIModelInfo, String
             default_model: "".to_string(),
             medical_model: "".to_string(),
             ollama_url: "http://127.0.0.1:11434".to_string(),
+            model_state: ModelState::Unloaded,
         })
     }
 }
 
+#[tauri::command]
+async fn set_active_model(model: String, state: State<'_, AppState>) -> Result<(), String> {
+    let mut medical_ai_guard = state.medical_ai.write().await;
+    let medical_ai = medical_ai_guard
+        .as_mut()
+        .ok_or_else(|| "AI service is not initialized".to_string())?;
+
+    let available_models = medical_ai
+        .list_models()
+        .await
+        .map_err(|e| format!("Failed to list available models: {}", e))?;
+
+    if !available_models.iter().any(|m| m == &model) {
+        return Err(format!("Model '{}' is not installed", model));
+    }
+
+    medical_ai.set_active_model(model);
+    Ok(())
+}
+
+/// Pulls `model`, streaming progress as `model-pull-progress-{model}` events so the
+/// frontend can render a download progress bar.
+#[tauri::command]
+async fn pull_model(
+    app_handle: tauri::AppHandle,
+    model: String,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    let event_name = format!("model-pull-progress-{}", model);
+
+    let medical_ai_guard = state.medical_ai.read().await;
+    let medical_ai = medical_ai_guard
+        .as_ref()
+        .ok_or_else(|| "AI service is not initialized".to_string())?;
+
+    medical_ai
+        .pull_model(&app_handle, &event_name, &model)
+        .await
+        .map_err(|e| format!("Failed to pull model: {}", e))
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct OllamaConnectionSettings {
+    scheme: Option<String>,
+    host: Option<String>,
+    port: Option<u16>,
+    api_key: Option<String>,
+}
+
+#[tauri::command]
+async fn set_ollama_config(
+    settings: OllamaConnectionSettings,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    let mut medical_ai_guard = state.medical_ai.write().await;
+    let medical_ai = medical_ai_guard
+        .as_mut()
+        .ok_or_else(|| "AI service is not initialized".to_string())?;
+
+    medical_ai
+        .update_ollama_config(settings.scheme, settings.host, settings.port, settings.api_key)
+        .await;
+    Ok(())
+}
+
+#[tauri::command]
+async fn set_generation_settings(
+    settings: GenerationSettings,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    let mut medical_ai_guard = state.medical_ai.write().await;
+    let medical_ai = medical_ai_guard
+        .as_mut()
+        .ok_or_else(|| "AI service is not initialized".to_string())?;
+
+    medical_ai.set_chat_options(ChatOptions {
+        num_ctx: Some(settings.num_ctx),
+        temperature: settings.temperature,
+        top_p: settings.top_p,
+        num_predict: settings.num_predict,
+        stop: settings.stop,
+    });
+    Ok(())
+}
+
+#[tauri::command]
+async fn set_rate_limit(requests_per_second: f64, state: State<'_, AppState>) -> Result<(), String> {
+    let mut medical_ai_guard = state.medical_ai.write().await;
+    let medical_ai = medical_ai_guard
+        .as_mut()
+        .ok_or_else(|| "AI service is not initialized".to_string())?;
+
+    medical_ai.set_rate_limit(Some(requests_per_second)).await;
+    Ok(())
+}
+
 // Helper functions
 #[allow(dead_code)]
 async fn generate_medical_response(query: &str, query_type: &str) -> Result<String, String> {
@@ -451,8 +775,9 @@ fn generate_general_response(query: &str) -> String {
     )
 }
 
-#[allow(dead_code)]
-fn detect_emergency(query: &str) -> Option<bool> {
+/// The single source of emergency-symptom keywords, used by `MedicalAI::analyze_medical_response`
+/// so the non-streaming and streaming query paths agree on what counts as an emergency.
+pub(crate) fn detect_emergency(query: &str) -> Option<bool> {
     let emergency_keywords = [
         "chest pain",
         "heart attack",
@@ -461,7 +786,12 @@ fn detect_emergency(query: &str) -> Option<bool> {
         "unconscious",
         "bleeding heavily",
         "can't breathe",
+        "difficulty breathing",
         "severe pain",
+        "severe allergic reaction",
+        "severe burn",
+        "choking",
+        "overdose",
         "emergency",
     ];
 
@@ -474,44 +804,6 @@ fn detect_emergency(query: &str) -> Option<bool> {
     None
 }
 
-#[allow(dead_code)]
-fn generate_medical_guidance(query: &str, query_type: &str) -> Option<MedicalGuidance> {
-    if detect_emergency(query).unwrap_or(false) {
-        return Some(MedicalGuidance {
-            severity: Some("high".to_string()),
-            recommendations: Some(vec![
-                "Seek immediate medical attention".to_string(),
-                "Contact emergency services if severe".to_string(),
-                "Do not delay professional medical care".to_string(),
-            ]),
-            emergency_action: Some("Contact emergency services immediately".to_string()),
-            follow_up: Some("Follow emergency protocols".to_string()),
-        });
-    }
-
-    match query_type {
-        "symptoms" => Some(MedicalGuidance {
-            severity: Some("moderate".to_string()),
-            recommendations: Some(vec![
-                "Monitor symptoms closely".to_string(),
-                "Consult healthcare provider if symptoms persist".to_string(),
-                "Keep a symptom diary".to_string(),
-            ]),
-            emergency_action: None,
-            follow_up: Some("Schedule follow-up if symptoms worsen".to_string()),
-        }),
-        _ => Some(MedicalGuidance {
-            severity: Some("low".to_string()),
-            recommendations: Some(vec![
-                "Consult with healthcare professionals for personalized advice".to_string(),
-                "Verify information with reliable medical sources".to_string(),
-            ]),
-            emergency_action: None,
-            follow_up: Some("Regular medical checkups recommended".to_string()),
-        }),
-    }
-}
-
 fn generate_fallback_response(query: &str, query_type: &str) -> String {
     match query_type {
         "symptoms" => generate_symptom_response(query),
@@ -529,13 +821,33 @@ pub fn run() {
         .invoke_handler(tauri::generate_handler![
             create_session,
             submit_medical_query,
+            submit_medical_query_chat_stream,
             get_system_health,
+            get_version_info,
             get_session_history,
+            delete_session,
             initialize_ai_service,
             stop_ai_service,
-            get_ai_models
+            get_ai_models,
+            set_active_model,
+            pull_model,
+            set_ollama_config,
+            set_generation_settings,
+            set_rate_limit
         ])
         .setup(|app| {
+            // Open the SQLite store under the app data dir before anything tries to use it.
+            let app_data_dir = app.path().app_data_dir()?;
+            std::fs::create_dir_all(&app_data_dir)?;
+            let db_path = app_data_dir.join("offlinedoctor.sqlite3");
+
+            match Store::open(&db_path) {
+                Ok(store) => {
+                    *app.state::<AppState>().store.lock().unwrap() = Some(store);
+                }
+                Err(e) => eprintln!("Failed to open SQLite store at {:?}: {}", db_path, e),
+            }
+
             // Initialize AI service on startup
             let app_handle = app.handle().clone();
 
@@ -1,6 +1,8 @@
-use crate::ollama_manager::{ChatMessage, OllamaManager};
+use crate::ollama_manager::{ChatMessage, ChatOptions, ModelState, OllamaManager};
+use crate::rag::VectorStore;
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
+use std::path::Path;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MedicalQuery {
@@ -27,31 +29,56 @@ pub struct MedicalGuidance {
 
 pub struct MedicalAI {
     ollama: OllamaManager,
+    /// Retrieval corpus grounding answers in vetted reference material. `None` when the
+    /// embedding model wasn't available at startup, in which case we fall back to plain chat.
+    rag_store: Option<VectorStore>,
+}
+
+/// The single authoritative medical-disclaimer system prompt, used as-is for the
+/// non-streaming and chat-streaming query paths and prepended with retrieved reference
+/// material when a RAG store is available.
+pub(crate) fn medical_system_prompt() -> String {
+    r#"You are a medical AI assistant designed to provide educational health information. Follow these guidelines:
+
+IMPORTANT DISCLAIMERS:
+- You provide educational information only, not professional medical advice
+- Always recommend consulting healthcare professionals for serious concerns
+- Never diagnose conditions or prescribe treatments
+- Emphasize emergency services for urgent situations
+
+RESPONSE FORMAT:
+Provide clear, informative responses about health topics while maintaining appropriate medical disclaimers.
+
+EMERGENCY DETECTION:
+If the user describes symptoms that could indicate a medical emergency (severe chest pain, difficulty breathing, severe allergic reactions, etc.), clearly state that they should seek immediate medical attention.
+
+MEDICAL GUIDANCE:
+- Provide general health information
+- Suggest when to see a healthcare provider
+- Offer basic wellness recommendations
+- Explain common medical terms
+
+Remember: You are an educational tool, not a replacement for professional medical care."#
+        .to_string()
 }
 
 impl MedicalAI {
     pub fn new(ollama: OllamaManager) -> Self {
-        Self { ollama }
+        Self {
+            ollama,
+            rag_store: None,
+        }
     }
 
-    pub async fn process_medical_query(&self, query: &MedicalQuery) -> Result<MedicalResponse> {
-        // Create a medical-focused system prompt
-        let system_prompt = self.get_medical_system_prompt();
+    /// Builds a `MedicalAI` backed by a RAG store persisted under `data_dir`, embedding
+    /// the bundled corpus on first run.
+    pub async fn with_rag(ollama: OllamaManager, data_dir: &Path) -> Self {
+        let rag_store = VectorStore::load_or_build(&ollama, data_dir).await;
+        Self { ollama, rag_store }
+    }
 
-        // Prepare the conversation
-        let messages = vec![
-            ChatMessage {
-                role: "system".to_string(),
-                content: system_prompt,
-            },
-            ChatMessage {
-                role: "user".to_string(),
-                content: format!(
-                    "Query Type: {}\nPatient Query: {}",
-                    query.query_type, query.query
-                ),
-            },
-        ];
+    pub async fn process_medical_query(&self, query: &MedicalQuery) -> Result<MedicalResponse> {
+        let messages = self.build_messages(query).await;
 
         // Get response from Ollama
         let ai_response = self.ollama.chat(messages).await?;
@@ -64,28 +91,52 @@ impl MedicalAI {
         Ok(medical_response)
     }
 
-    fn get_medical_system_prompt(&self) -> String {
-        r#"You are a medical AI assistant designed to provide educational health information. Follow these guidelines:
+    /// Streams the reply token-by-token to `event_name`, then runs the same emergency
+    /// detection and guidance analysis as `process_medical_query` over the completed text.
+    pub async fn process_medical_query_stream(
+        &self,
+        query: &MedicalQuery,
+        app_handle: &tauri::AppHandle,
+        event_name: &str,
+    ) -> Result<MedicalResponse> {
+        let messages = self.build_messages(query).await;
 
-IMPORTANT DISCLAIMERS:
-- You provide educational information only, not professional medical advice
-- Always recommend consulting healthcare professionals for serious concerns
-- Never diagnose conditions or prescribe treatments
-- Emphasize emergency services for urgent situations
+        let ai_response = self
+            .ollama
+            .chat_stream(app_handle, event_name, messages)
+            .await?;
 
-RESPONSE FORMAT:
-Provide clear, informative responses about health topics while maintaining appropriate medical disclaimers.
+        self.analyze_medical_response(&ai_response, &query.query)
+            .await
+    }
 
-EMERGENCY DETECTION:
-If the user describes symptoms that could indicate a medical emergency (severe chest pain, difficulty breathing, severe allergic reactions, etc.), clearly state that they should seek immediate medical attention.
+    async fn build_messages(&self, query: &MedicalQuery) -> Vec<ChatMessage> {
+        let mut system_prompt = medical_system_prompt();
 
-MEDICAL GUIDANCE:
-- Provide general health information
-- Suggest when to see a healthcare provider
-- Offer basic wellness recommendations
-- Explain common medical terms
+        if let Some(ref rag_store) = self.rag_store {
+            let chunks = rag_store.retrieve(&self.ollama, &query.query).await;
+            if !chunks.is_empty() {
+                system_prompt = format!(
+                    "Use the following reference material to help answer the patient's query:\n\n{}\n\n{}",
+                    chunks.join("\n\n"),
+                    system_prompt
+                );
+            }
+        }
 
-Remember: You are an educational tool, not a replacement for professional medical care."#.to_string()
+        vec![
+            ChatMessage {
+                role: "system".to_string(),
+                content: system_prompt,
+            },
+            ChatMessage {
+                role: "user".to_string(),
+                content: format!(
+                    "Query Type: {}\nPatient Query: {}",
+                    query.query_type, query.query
+                ),
+            },
+        ]
     }
 
     async fn analyze_medical_response(
@@ -93,25 +144,9 @@ Remember: You are an educational tool, not a replacement for professional medica
         ai_response: &str,
         original_query: &str,
     ) -> Result<MedicalResponse> {
-        // Simple keyword-based emergency detection
-        let emergency_keywords = [
-            "chest pain",
-            "difficulty breathing",
-            "severe pain",
-            "unconscious",
-            "bleeding heavily",
-            "severe allergic reaction",
-            "heart attack",
-            "stroke",
-            "severe burn",
-            "choking",
-            "overdose",
-        ];
-
-        let query_lower = original_query.to_lowercase();
-        let emergency_detected = emergency_keywords
-            .iter()
-            .any(|keyword| query_lower.contains(keyword));
+        // Uses the single keyword list in `lib.rs::detect_emergency`, so the non-streaming
+        // and streaming query paths agree on what counts as an emergency.
+        let emergency_detected = crate::detect_emergency(original_query).unwrap_or(false);
 
         // Extract recommendations (simple implementation)
         let recommendations = self.extract_recommendations(ai_response);
@@ -179,6 +214,86 @@ Remember: You are an educational tool, not a replacement for professional medica
     }
 
     pub async fn ensure_model_ready(&self) -> Result<()> {
-        self.ollama.ensure_model("tinyllama:latest").await
+        self.ollama.ensure_model_ready("tinyllama:latest").await
+    }
+
+    /// The active model's current load state, for UI-facing "loading model…" indicators.
+    pub async fn model_state(&self) -> ModelState {
+        self.ollama.model_state().await
+    }
+
+    /// Pulls `model`, streaming progress to `event_name` so the frontend can render a
+    /// download progress bar.
+    pub async fn pull_model(
+        &self,
+        app_handle: &tauri::AppHandle,
+        event_name: &str,
+        model: &str,
+    ) -> Result<()> {
+        self.ollama.pull_model_stream(app_handle, event_name, model).await
+    }
+
+    /// Lists the models Ollama currently has installed.
+    pub async fn list_models(&self) -> Result<Vec<String>> {
+        self.ollama.list_models().await
+    }
+
+    /// The model currently used for chat completions.
+    pub fn current_model(&self) -> &str {
+        self.ollama.current_model()
+    }
+
+    /// The underlying Ollama connection, for callers (e.g. semantic search) that need to
+    /// embed text against the same configured endpoint, scheme, and credentials as chat.
+    pub fn ollama(&self) -> &OllamaManager {
+        &self.ollama
+    }
+
+    /// The base URL of the Ollama endpoint backing this instance.
+    pub fn ollama_url(&self) -> String {
+        self.ollama.base_url()
+    }
+
+    /// Switches the active chat model. Callers should validate `model` against
+    /// `list_models` first.
+    pub fn set_active_model(&mut self, model: String) {
+        self.ollama.set_model(model);
+    }
+
+    /// Replaces the sampling/context options applied to every subsequent query, the
+    /// single source of truth fed by the `set_generation_settings` command.
+    pub fn set_chat_options(&mut self, options: ChatOptions) {
+        self.ollama.set_chat_options(options);
+    }
+
+    /// The bearer token configured for the active Ollama endpoint, if any.
+    pub fn ollama_api_key(&self) -> Option<String> {
+        self.ollama.api_key().map(|s| s.to_string())
+    }
+
+    /// Updates the Ollama endpoint and/or credentials in use.
+    pub async fn update_ollama_config(
+        &mut self,
+        scheme: Option<String>,
+        host: Option<String>,
+        port: Option<u16>,
+        api_key: Option<String>,
+    ) {
+        self.ollama
+            .update_endpoint(scheme, host, port, api_key)
+            .await;
+    }
+
+    /// The Ollama server's own version, for diagnostics.
+    pub async fn ollama_version(&self) -> Result<String> {
+        self.ollama.server_version().await
+    }
+
+    /// Replaces the request-rate cap applied to every call into the Ollama backend.
+    /// `None` removes the cap.
+    pub async fn set_rate_limit(&mut self, max_requests_per_second: Option<f64>) {
+        self.ollama
+            .set_max_requests_per_second(max_requests_per_second)
+            .await;
     }
 }
@@ -1,34 +1,143 @@
+use crate::rate_limiter::RateLimiter;
 use anyhow::{anyhow, Result};
+use futures_util::StreamExt;
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 use std::process::{Command, Stdio};
 use std::sync::Arc;
-use tauri::Manager;
+use tauri::{Emitter, Manager};
 use tokio::sync::Mutex;
 use tokio::time::{sleep, Duration};
 
+/// Stand-in "no meaningful limit" rate used when `max_requests_per_second` is `None`, so
+/// the same token-bucket limiter can back both throttled and unthrottled configurations.
+const UNLIMITED_REQUESTS_PER_SECOND: f64 = 1_000.0;
+
+/// Default throttle applied automatically once a remote host or API key is configured,
+/// so pointing at a shared endpoint doesn't risk overwhelming it by default.
+const DEFAULT_REMOTE_REQUESTS_PER_SECOND: f64 = 2.0;
+
+/// Maximum retries `embed` performs after a 429/5xx from the embeddings endpoint before
+/// giving up, with exponential backoff between attempts.
+const MAX_EMBED_RETRIES: u32 = 3;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OllamaConfig {
+    /// `http` or `https`. `https` is required for most reverse-proxied remote endpoints.
+    pub scheme: String,
     pub host: String,
     pub port: u16,
     pub model: String,
+    /// Bearer token for authenticated/remote Ollama endpoints.
+    pub api_key: Option<String>,
+    /// True when `host`/`port` came from explicit remote configuration (env var or the
+    /// `set_ollama_config` command) rather than the embedded-instance default.
+    #[serde(skip)]
+    pub remote_configured: bool,
+    /// Sampling/context options applied to every `chat` request.
+    pub chat_options: ChatOptions,
+    /// Seconds of no bytes being read before a chat request gives up. Applied as
+    /// `reqwest`'s read timeout, which resets on every chunk received, so a slow-but-alive
+    /// cold model load doesn't get cut off the way a flat request timeout would.
+    pub low_speed_timeout_secs: u64,
+    /// Caps requests issued by `chat`, `list_models`, `pull_model`, and `embed`.
+    /// `None` means unthrottled, which is the default for a local embedded instance;
+    /// configuring a remote host or API key switches to a conservative default so a
+    /// shared endpoint isn't overwhelmed.
+    pub max_requests_per_second: Option<f64>,
 }
 
 impl Default for OllamaConfig {
     fn default() -> Self {
         Self {
+            scheme: "http".to_string(),
             host: "127.0.0.1".to_string(),
             port: 11434,
             model: "tinyllama:latest".to_string(),
+            api_key: None,
+            remote_configured: false,
+            // Low temperature keeps medical answers consistent across repeated queries
+            // rather than creatively varied, which matters more for health information
+            // than for general chat.
+            chat_options: ChatOptions {
+                num_ctx: Some(4096),
+                temperature: Some(0.2),
+                ..Default::default()
+            },
+            low_speed_timeout_secs: 30,
+            max_requests_per_second: None,
         }
     }
 }
 
+impl OllamaConfig {
+    /// Builds the default config, then overlays `OLLAMA_URL`/`OLLAMA_API_KEY` from the
+    /// environment so users running a remote or authenticated Ollama don't need to recompile.
+    pub fn from_env() -> Self {
+        let mut config = Self::default();
+
+        if let Ok(api_key) = std::env::var("OLLAMA_API_KEY") {
+            if !api_key.is_empty() {
+                config.api_key = Some(api_key);
+            }
+        }
+
+        if let Ok(url) = std::env::var("OLLAMA_URL") {
+            if let Some((scheme, host, port)) = parse_scheme_host_port(&url) {
+                config.scheme = scheme;
+                config.host = host;
+                config.port = port;
+                config.remote_configured = true;
+            }
+        }
+
+        if config.remote_configured || config.api_key.is_some() {
+            config.max_requests_per_second = Some(DEFAULT_REMOTE_REQUESTS_PER_SECOND);
+        }
+
+        config
+    }
+}
+
+/// Parses a `scheme://host:port` or bare `host:port` string into its scheme (defaulting
+/// to `http` when absent), host, and port parts.
+fn parse_scheme_host_port(url: &str) -> Option<(String, String, u16)> {
+    let scheme = if let Some((scheme, _)) = url.split_once("://") {
+        scheme.to_string()
+    } else {
+        "http".to_string()
+    };
+    let without_scheme = url.split("://").last().unwrap_or(url);
+    let host_port = without_scheme.split('/').next().unwrap_or(without_scheme);
+    let (host, port) = host_port.rsplit_once(':')?;
+    let port: u16 = port.parse().ok()?;
+    Some((scheme, host.to_string(), port))
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ChatRequest {
     pub model: String,
     pub messages: Vec<ChatMessage>,
     pub stream: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub options: Option<ChatOptions>,
+}
+
+/// Sampling/context options sent as Ollama's `options` object. `ChatOptions::default()`
+/// leaves every field unset; `OllamaConfig::default()` sets `num_ctx` to 4096 since Ollama
+/// has no API to query a model's actual max context.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ChatOptions {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub num_ctx: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub top_p: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub num_predict: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stop: Option<Vec<String>>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -43,18 +152,60 @@ pub struct ChatResponse {
     pub done: bool,
 }
 
+/// One status update from `/api/pull`'s streamed response, emitted to the frontend so a
+/// model download can show a real progress bar instead of a spinner.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PullProgress {
+    pub status: String,
+    pub completed: Option<u64>,
+    pub total: Option<u64>,
+    pub percentage: Option<f64>,
+}
+
+/// Where the active chat model sits in its load lifecycle, surfaced to the UI so it can
+/// show a "loading model…" indicator during a cold start instead of looking frozen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ModelState {
+    Unloaded,
+    Loading,
+    Ready,
+}
+
 pub struct OllamaManager {
     config: OllamaConfig,
     process: Arc<Mutex<Option<std::process::Child>>>,
     client: reqwest::Client,
+    model_state: Arc<Mutex<ModelState>>,
+    rate_limiter: Arc<RateLimiter>,
+}
+
+/// Distinguishes transient `embed` failures worth retrying (429/5xx) from permanent
+/// ones (connection failure, bad response), so `embed` knows when to back off and
+/// retry versus give up immediately.
+enum EmbedError {
+    Retryable(anyhow::Error),
+    Fatal(anyhow::Error),
 }
 
 impl OllamaManager {
     pub fn new(config: OllamaConfig) -> Self {
+        let client = reqwest::Client::builder()
+            .read_timeout(Duration::from_secs(config.low_speed_timeout_secs))
+            .build()
+            .unwrap_or_default();
+
+        let rate_limiter = Arc::new(RateLimiter::new(
+            config
+                .max_requests_per_second
+                .unwrap_or(UNLIMITED_REQUESTS_PER_SECOND),
+        ));
+
         Self {
             config,
             process: Arc::new(Mutex::new(None)),
-            client: reqwest::Client::new(),
+            client,
+            model_state: Arc::new(Mutex::new(ModelState::Unloaded)),
+            rate_limiter,
         }
     }
 
@@ -118,9 +269,9 @@ impl OllamaManager {
 
     /// Check if Ollama is running (embedded or external)
     pub async fn health_check(&self) -> Result<bool> {
-        let url = format!("http://{}:{}/api/tags", self.config.host, self.config.port);
+        let url = format!("{}/api/tags", self.base_url());
 
-        match self.client.get(&url).send().await {
+        match self.authorize(self.client.get(&url)).send().await {
             Ok(response) => Ok(response.status().is_success()),
             Err(_) => Ok(false),
         }
@@ -142,11 +293,210 @@ impl OllamaManager {
         Ok(())
     }
 
+    /// Ensures an embedding model (e.g. `nomic-embed-text`) is installed, pulling it if needed.
+    pub async fn ensure_embedding_model(&self, model: &str) -> Result<()> {
+        self.ensure_model(model).await
+    }
+
+    /// Forces the active model into memory with an empty-prompt chat request, so the
+    /// (often slow) cold load happens up front rather than during the user's first query.
+    pub async fn preload_model(&self) -> Result<()> {
+        self.chat(vec![ChatMessage {
+            role: "user".to_string(),
+            content: String::new(),
+        }])
+        .await?;
+        Ok(())
+    }
+
+    /// The active model's current load state, for UI-facing "loading model…" indicators.
+    pub async fn model_state(&self) -> ModelState {
+        *self.model_state.lock().await
+    }
+
+    /// Ensures `model` is installed, then preloads the active chat model (`current_model`)
+    /// into memory, tracking progress through `ModelState` so callers can report it to the
+    /// UI. When `model` is not the active model, `model` itself is not preloaded.
+    pub async fn ensure_model_ready(&self, model: &str) -> Result<()> {
+        *self.model_state.lock().await = ModelState::Loading;
+
+        if let Err(e) = self.ensure_model(model).await {
+            *self.model_state.lock().await = ModelState::Unloaded;
+            return Err(e);
+        }
+
+        if let Err(e) = self.preload_model().await {
+            *self.model_state.lock().await = ModelState::Unloaded;
+            return Err(e);
+        }
+
+        *self.model_state.lock().await = ModelState::Ready;
+        Ok(())
+    }
+
+    /// Embeds `text` with `model` via `/api/embeddings`, retrying with exponential
+    /// backoff on 429/5xx responses (transient throttling/overload) up to
+    /// `MAX_EMBED_RETRIES` times.
+    pub async fn embed(&self, model: &str, text: &str) -> Result<Vec<f32>> {
+        let mut attempt = 0;
+        loop {
+            match self.embed_once(model, text).await {
+                Ok(embedding) => return Ok(embedding),
+                Err(EmbedError::Retryable(e)) if attempt < MAX_EMBED_RETRIES => {
+                    attempt += 1;
+                    let backoff = Duration::from_millis(200 * 2u64.pow(attempt));
+                    eprintln!("Embeddings request failed ({}), retrying in {:?}", e, backoff);
+                    sleep(backoff).await;
+                }
+                Err(EmbedError::Retryable(e)) | Err(EmbedError::Fatal(e)) => return Err(e),
+            }
+        }
+    }
+
+    async fn embed_once(&self, model: &str, text: &str) -> Result<Vec<f32>, EmbedError> {
+        self.rate_limiter.acquire().await;
+        let url = format!("{}/api/embeddings", self.base_url());
+
+        let request = serde_json::json!({
+            "model": model,
+            "prompt": text,
+        });
+
+        let response = self
+            .authorize(self.client.post(&url).json(&request))
+            .send()
+            .await
+            .map_err(|e| EmbedError::Fatal(anyhow!(e)))?;
+
+        let status = response.status();
+        if status.as_u16() == 429 || status.is_server_error() {
+            return Err(EmbedError::Retryable(anyhow!(
+                "Embeddings request failed: {}",
+                status
+            )));
+        }
+        if !status.is_success() {
+            return Err(EmbedError::Fatal(anyhow!(
+                "Embeddings request failed: {}",
+                status
+            )));
+        }
+
+        let json: serde_json::Value = response
+            .json()
+            .await
+            .map_err(|e| EmbedError::Fatal(anyhow!(e)))?;
+        let embedding = json["embedding"]
+            .as_array()
+            .ok_or_else(|| EmbedError::Fatal(anyhow!("Embeddings response missing 'embedding' array")))?
+            .iter()
+            .filter_map(|v| v.as_f64())
+            .map(|v| v as f32)
+            .collect();
+
+        Ok(embedding)
+    }
+
+    /// Base URL of the configured Ollama endpoint.
+    pub fn base_url(&self) -> String {
+        format!("{}://{}:{}", self.config.scheme, self.config.host, self.config.port)
+    }
+
+    /// Currently configured chat model.
+    pub fn current_model(&self) -> &str {
+        &self.config.model
+    }
+
+    /// Switches the model used by subsequent `chat` calls.
+    pub fn set_model(&mut self, model: String) {
+        self.config.model = model;
+    }
+
+    /// Replaces the sampling/context options applied to subsequent `chat` calls.
+    pub fn set_chat_options(&mut self, options: ChatOptions) {
+        self.config.chat_options = options;
+    }
+
+    /// The bearer token attached to outgoing requests, if any.
+    pub fn api_key(&self) -> Option<&str> {
+        self.config.api_key.as_deref()
+    }
+
+    /// Updates the endpoint and/or credentials this manager talks to, e.g. from a
+    /// user-supplied `set_ollama_config` command. Applies the default remote throttle
+    /// (see `max_requests_per_second`) the first time this becomes a remote or
+    /// authenticated endpoint, unless a limit was already configured explicitly.
+    pub async fn update_endpoint(
+        &mut self,
+        scheme: Option<String>,
+        host: Option<String>,
+        port: Option<u16>,
+        api_key: Option<String>,
+    ) {
+        if let Some(scheme) = scheme {
+            self.config.scheme = scheme;
+            self.config.remote_configured = true;
+        }
+        if let Some(host) = host {
+            self.config.host = host;
+            self.config.remote_configured = true;
+        }
+        if let Some(port) = port {
+            self.config.port = port;
+            self.config.remote_configured = true;
+        }
+        if api_key.is_some() {
+            self.config.api_key = api_key;
+        }
+
+        if self.config.max_requests_per_second.is_none()
+            && (self.config.remote_configured || self.config.api_key.is_some())
+        {
+            self.set_max_requests_per_second(Some(DEFAULT_REMOTE_REQUESTS_PER_SECOND))
+                .await;
+        }
+    }
+
+    /// Replaces the request-rate cap applied to `chat`, `list_models`, `pull_model`, and
+    /// `embed`. `None` removes the cap.
+    pub async fn set_max_requests_per_second(&mut self, limit: Option<f64>) {
+        self.config.max_requests_per_second = limit;
+        self.rate_limiter
+            .set_rate(limit.unwrap_or(UNLIMITED_REQUESTS_PER_SECOND))
+            .await;
+    }
+
+    /// Attaches the configured bearer token, if any, to a request.
+    pub fn authorize(&self, builder: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+        match &self.config.api_key {
+            Some(key) => builder.bearer_auth(key),
+            None => builder,
+        }
+    }
+
+    /// Queries the Ollama server's own version via `/api/version`.
+    pub async fn server_version(&self) -> Result<String> {
+        let url = format!("{}/api/version", self.base_url());
+
+        let response = self.authorize(self.client.get(&url)).send().await?;
+
+        if !response.status().is_success() {
+            return Err(anyhow!("Failed to get Ollama version: {}", response.status()));
+        }
+
+        let json: serde_json::Value = response.json().await?;
+        json["version"]
+            .as_str()
+            .map(|s| s.to_string())
+            .ok_or_else(|| anyhow!("Ollama version response missing 'version' field"))
+    }
+
     /// List available models
     pub async fn list_models(&self) -> Result<Vec<String>> {
-        let url = format!("http://{}:{}/api/tags", self.config.host, self.config.port);
+        self.rate_limiter.acquire().await;
+        let url = format!("{}/api/tags", self.base_url());
 
-        let response = self.client.get(&url).send().await?;
+        let response = self.authorize(self.client.get(&url)).send().await?;
 
         if !response.status().is_success() {
             return Err(anyhow!("Failed to list models: {}", response.status()));
@@ -163,35 +513,117 @@ impl OllamaManager {
         Ok(models)
     }
 
-    /// Pull a model
+    /// Pulls a model, consuming `/api/pull`'s streamed status objects and discarding their
+    /// progress. Callers that want progress reported to the UI should use
+    /// `pull_model_stream` instead.
     pub async fn pull_model(&self, model: &str) -> Result<()> {
-        let url = format!("http://{}:{}/api/pull", self.config.host, self.config.port);
+        self.pull_model_inner(model, None).await
+    }
+
+    /// Pulls a model, emitting a `PullProgress` event to `event_name` for each status
+    /// object `/api/pull` streams back, so the frontend can render a real progress bar.
+    pub async fn pull_model_stream(
+        &self,
+        app_handle: &tauri::AppHandle,
+        event_name: &str,
+        model: &str,
+    ) -> Result<()> {
+        self.pull_model_inner(model, Some((app_handle, event_name)))
+            .await
+    }
+
+    async fn pull_model_inner(
+        &self,
+        model: &str,
+        emit_to: Option<(&tauri::AppHandle, &str)>,
+    ) -> Result<()> {
+        self.rate_limiter.acquire().await;
+        let url = format!("{}/api/pull", self.base_url());
 
         let request = serde_json::json!({
             "name": model
         });
 
-        let response = self.client.post(&url).json(&request).send().await?;
+        let response = self
+            .authorize(self.client.post(&url).json(&request))
+            .send()
+            .await?;
 
         if !response.status().is_success() {
             return Err(anyhow!("Failed to pull model: {}", response.status()));
         }
 
+        let mut buffer = String::new();
+        let mut byte_stream = response.bytes_stream();
+
+        while let Some(chunk) = byte_stream.next().await {
+            let bytes = chunk?;
+            buffer.push_str(&String::from_utf8_lossy(&bytes));
+
+            while let Some(newline_pos) = buffer.find('\n') {
+                let line = buffer[..newline_pos].trim().to_string();
+                buffer.drain(..=newline_pos);
+
+                if line.is_empty() {
+                    continue;
+                }
+
+                let value: serde_json::Value = match serde_json::from_str(&line) {
+                    Ok(parsed) => parsed,
+                    Err(_) => continue,
+                };
+
+                if let Some(error) = value["error"].as_str() {
+                    return Err(anyhow!("Ollama pull failed: {}", error));
+                }
+
+                let status = value["status"].as_str().unwrap_or_default().to_string();
+                let total = value["total"].as_u64();
+                let completed = value["completed"].as_u64();
+                let percentage = match (completed, total) {
+                    (Some(completed), Some(total)) if total > 0 => {
+                        Some(completed as f64 / total as f64 * 100.0)
+                    }
+                    _ => None,
+                };
+                let done = status == "success";
+
+                if let Some((app_handle, event_name)) = emit_to {
+                    let _ = app_handle.emit(
+                        event_name,
+                        &PullProgress {
+                            status,
+                            completed,
+                            total,
+                            percentage,
+                        },
+                    );
+                }
+
+                if done {
+                    println!("Model {} pulled successfully", model);
+                    return Ok(());
+                }
+            }
+        }
+
         println!("Model {} pulled successfully", model);
         Ok(())
     }
 
     /// Send a chat message
     pub async fn chat(&self, messages: Vec<ChatMessage>) -> Result<String> {
-        let url = format!("http://{}:{}/api/chat", self.config.host, self.config.port);
+        self.rate_limiter.acquire().await;
+        let url = format!("{}/api/chat", self.base_url());
 
         let request = ChatRequest {
             model: self.config.model.clone(),
             messages,
             stream: false,
+            options: Some(self.config.chat_options.clone()),
         };
 
-        let response = self.client.post(&url).json(&request).send().await?;
+        let response = self.authorize(self.client.post(&url).json(&request)).send().await?;
 
         if !response.status().is_success() {
             return Err(anyhow!("Chat request failed: {}", response.status()));
@@ -201,6 +633,68 @@ impl OllamaManager {
         Ok(chat_response.message.content)
     }
 
+    /// Send a chat message, streaming each incremental token to `event_name` as it
+    /// arrives, and returning the fully assembled reply once the server reports `done`.
+    pub async fn chat_stream(
+        &self,
+        app_handle: &tauri::AppHandle,
+        event_name: &str,
+        messages: Vec<ChatMessage>,
+    ) -> Result<String> {
+        self.rate_limiter.acquire().await;
+        let url = format!("{}/api/chat", self.base_url());
+
+        let request = ChatRequest {
+            model: self.config.model.clone(),
+            messages,
+            stream: true,
+            options: Some(self.config.chat_options.clone()),
+        };
+
+        let response = self
+            .authorize(self.client.post(&url).json(&request))
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(anyhow!("Chat request failed: {}", response.status()));
+        }
+
+        let mut accumulated = String::new();
+        let mut buffer = String::new();
+        let mut byte_stream = response.bytes_stream();
+
+        while let Some(chunk) = byte_stream.next().await {
+            let bytes = chunk?;
+            buffer.push_str(&String::from_utf8_lossy(&bytes));
+
+            while let Some(newline_pos) = buffer.find('\n') {
+                let line = buffer[..newline_pos].trim().to_string();
+                buffer.drain(..=newline_pos);
+
+                if line.is_empty() {
+                    continue;
+                }
+
+                let chunk_response: ChatResponse = match serde_json::from_str(&line) {
+                    Ok(parsed) => parsed,
+                    Err(_) => continue,
+                };
+
+                if !chunk_response.message.content.is_empty() {
+                    accumulated.push_str(&chunk_response.message.content);
+                    let _ = app_handle.emit(event_name, &chunk_response.message.content);
+                }
+
+                if chunk_response.done {
+                    return Ok(accumulated);
+                }
+            }
+        }
+
+        Ok(accumulated)
+    }
+
     /// Get the path to the bundled Ollama binary
     fn get_bundled_ollama_path(&self, app_handle: &tauri::AppHandle) -> Result<PathBuf> {
         let resource_dir = app_handle.path().resource_dir()?;
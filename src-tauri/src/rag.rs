@@ -0,0 +1,167 @@
+use crate::ollama_manager::OllamaManager;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+const EMBEDDING_MODEL: &str = "nomic-embed-text";
+const CHUNK_WORD_SIZE: usize = 500;
+const CHUNK_OVERLAP: usize = 50;
+const TOP_K: usize = 4;
+
+/// A small bundled corpus of vetted health information used to ground answers instead
+/// of relying purely on the model's parametric memory.
+const BUNDLED_DOCUMENTS: &[&str] = &[
+    "Seasonal influenza typically causes fever, chills, muscle aches, cough, congestion, \
+     and fatigue that develop abruptly and last about a week. Rest, fluids, and \
+     over-the-counter fever reducers are usually sufficient for otherwise healthy adults. \
+     Seek care promptly for difficulty breathing, persistent chest pain, confusion, or \
+     symptoms that improve then sharply worsen.",
+    "Hypertension is usually defined as a sustained blood pressure at or above 130/80 mmHg. \
+     It is often asymptomatic, which is why routine screening matters. Lifestyle measures \
+     such as reducing sodium intake, regular exercise, and limiting alcohol can lower blood \
+     pressure, and many people also need medication. Uncontrolled hypertension raises the \
+     risk of heart attack, stroke, and kidney disease.",
+    "Migraine headaches are often unilateral, throbbing, and accompanied by nausea, and \
+     sensitivity to light or sound. Common triggers include poor sleep, skipped meals, \
+     stress, and certain foods. Over-the-counter pain relievers can help mild attacks; \
+     frequent or severe migraines may warrant preventive medication prescribed by a doctor.",
+    "Type 2 diabetes develops when the body becomes resistant to insulin or doesn't produce \
+     enough of it, leading to elevated blood glucose. Early symptoms can be subtle: \
+     increased thirst, frequent urination, and fatigue. Management centers on diet, \
+     exercise, blood sugar monitoring, and often oral medication or insulin.",
+    "Gastroesophageal reflux disease (GERD) causes stomach acid to back up into the \
+     esophagus, producing heartburn and regurgitation, especially after meals or when \
+     lying down. Smaller meals, avoiding trigger foods, and not lying down right after \
+     eating can help; persistent symptoms despite these measures should be evaluated by a \
+     clinician, since long-standing GERD can damage the esophagus.",
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct StoredChunk {
+    text: String,
+    /// Normalized at insert time so retrieval similarity is a plain dot product.
+    embedding: Vec<f32>,
+}
+
+/// On-disk vector store backing retrieval-augmented generation: splits the bundled
+/// corpus into overlapping chunks, embeds them once via Ollama, and persists the result
+/// under the app data dir so subsequent launches don't need to re-embed.
+pub struct VectorStore {
+    chunks: Vec<StoredChunk>,
+    path: PathBuf,
+}
+
+impl VectorStore {
+    /// Loads a previously persisted store, or builds one from the bundled corpus.
+    /// Returns `None` if the embedding model is unavailable, so callers can fall back
+    /// to plain chat without reference material.
+    pub async fn load_or_build(ollama: &OllamaManager, data_dir: &Path) -> Option<Self> {
+        let path = data_dir.join("rag_store.json");
+
+        if let Ok(bytes) = std::fs::read(&path) {
+            if let Ok(chunks) = serde_json::from_slice::<Vec<StoredChunk>>(&bytes) {
+                return Some(Self { chunks, path });
+            }
+        }
+
+        if let Err(e) = ollama.ensure_embedding_model(EMBEDDING_MODEL).await {
+            eprintln!("Skipping RAG store, embedding model unavailable: {}", e);
+            return None;
+        }
+
+        let mut chunks = Vec::new();
+        for document in BUNDLED_DOCUMENTS {
+            for chunk_text in split_into_chunks(document, CHUNK_WORD_SIZE, CHUNK_OVERLAP) {
+                match ollama.embed(EMBEDDING_MODEL, &chunk_text).await {
+                    Ok(embedding) => {
+                        if let Some(normalized) = normalize(&embedding) {
+                            chunks.push(StoredChunk {
+                                text: chunk_text,
+                                embedding: normalized,
+                            });
+                        }
+                    }
+                    Err(e) => {
+                        eprintln!("Failed to build RAG store: {}", e);
+                        return None;
+                    }
+                }
+            }
+        }
+
+        let store = Self { chunks, path };
+        store.persist();
+        Some(store)
+    }
+
+    fn persist(&self) {
+        let Ok(json) = serde_json::to_vec(&self.chunks) else {
+            return;
+        };
+        if let Some(parent) = self.path.parent() {
+            let _ = std::fs::create_dir_all(parent);
+        }
+        if let Err(e) = std::fs::write(&self.path, json) {
+            eprintln!("Failed to persist RAG store to {:?}: {}", self.path, e);
+        }
+    }
+
+    /// Returns the top-k most relevant chunks of reference material for `query`, or an
+    /// empty list if the embedding model is unavailable at query time.
+    pub async fn retrieve(&self, ollama: &OllamaManager, query: &str) -> Vec<String> {
+        let query_embedding = match ollama.embed(EMBEDDING_MODEL, query).await {
+            Ok(embedding) => match normalize(&embedding) {
+                Some(normalized) => normalized,
+                None => return Vec::new(),
+            },
+            Err(e) => {
+                eprintln!("Skipping retrieval, failed to embed query: {}", e);
+                return Vec::new();
+            }
+        };
+
+        let mut scored: Vec<(f32, &str)> = self
+            .chunks
+            .iter()
+            .map(|chunk| (dot(&query_embedding, &chunk.embedding), chunk.text.as_str()))
+            .collect();
+
+        scored.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(std::cmp::Ordering::Equal));
+        scored.truncate(TOP_K);
+        scored.into_iter().map(|(_, text)| text.to_string()).collect()
+    }
+}
+
+fn normalize(vector: &[f32]) -> Option<Vec<f32>> {
+    let norm: f32 = vector.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm == 0.0 {
+        return None;
+    }
+    Some(vector.iter().map(|x| x / norm).collect())
+}
+
+fn dot(a: &[f32], b: &[f32]) -> f32 {
+    a.iter().zip(b.iter()).map(|(x, y)| x * y).sum()
+}
+
+/// Splits `text` into overlapping chunks of roughly `chunk_size` words.
+fn split_into_chunks(text: &str, chunk_size: usize, overlap: usize) -> Vec<String> {
+    let words: Vec<&str> = text.split_whitespace().collect();
+    if words.is_empty() {
+        return Vec::new();
+    }
+
+    let mut chunks = Vec::new();
+    let mut start = 0;
+    let step = chunk_size.saturating_sub(overlap).max(1);
+
+    while start < words.len() {
+        let end = (start + chunk_size).min(words.len());
+        chunks.push(words[start..end].join(" "));
+        if end == words.len() {
+            break;
+        }
+        start += step;
+    }
+
+    chunks
+}
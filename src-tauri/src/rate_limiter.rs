@@ -0,0 +1,67 @@
+use tokio::sync::Mutex;
+use tokio::time::{sleep, Duration, Instant};
+
+/// A simple token-bucket limiter: capacity = ceil(rate), refilling at `rate` tokens per
+/// second. Shared across calls into a single Ollama backend so a burst of queries (or an
+/// auto-retry loop) can't overwhelm a locally-hosted model.
+pub struct RateLimiter {
+    inner: Mutex<Inner>,
+}
+
+struct Inner {
+    rate: f64,
+    capacity: f64,
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl RateLimiter {
+    pub fn new(rate_per_second: f64) -> Self {
+        let rate = rate_per_second.max(0.01);
+        let capacity = rate.ceil();
+
+        Self {
+            inner: Mutex::new(Inner {
+                rate,
+                capacity,
+                tokens: capacity,
+                last_refill: Instant::now(),
+            }),
+        }
+    }
+
+    /// Updates the refill rate (and the bucket capacity, which tracks it), clamping
+    /// currently-held tokens down if the new capacity is smaller.
+    pub async fn set_rate(&self, rate_per_second: f64) {
+        let mut inner = self.inner.lock().await;
+        inner.rate = rate_per_second.max(0.01);
+        inner.capacity = inner.rate.ceil();
+        inner.tokens = inner.tokens.min(inner.capacity);
+    }
+
+    /// Waits until a token is available, refilling the bucket based on elapsed time.
+    pub async fn acquire(&self) {
+        loop {
+            let wait = {
+                let mut inner = self.inner.lock().await;
+                let now = Instant::now();
+                let elapsed = now.duration_since(inner.last_refill).as_secs_f64();
+                inner.tokens = (inner.tokens + elapsed * inner.rate).min(inner.capacity);
+                inner.last_refill = now;
+
+                if inner.tokens >= 1.0 {
+                    inner.tokens -= 1.0;
+                    None
+                } else {
+                    let deficit = 1.0 - inner.tokens;
+                    Some(Duration::from_secs_f64(deficit / inner.rate))
+                }
+            };
+
+            match wait {
+                None => return,
+                Some(duration) => sleep(duration).await,
+            }
+        }
+    }
+}
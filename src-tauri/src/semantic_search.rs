@@ -0,0 +1,94 @@
+use crate::ollama_manager::OllamaManager;
+use crate::RelatedCondition;
+
+const EMBEDDING_MODEL: &str = "nomic-embed-text";
+
+/// Bundled medical conditions embedded once at startup to back semantic retrieval.
+const BUNDLED_CONDITIONS: &[(&str, &str, &str)] = &[
+    ("cond-migraine", "Migraine", "Recurrent throbbing headache often with nausea and light sensitivity"),
+    ("cond-common-cold", "Common Cold", "Mild viral upper respiratory infection with congestion and cough"),
+    ("cond-influenza", "Influenza", "Viral infection causing fever, body aches, fatigue, and respiratory symptoms"),
+    ("cond-gerd", "Gastroesophageal Reflux Disease", "Chronic acid reflux causing heartburn and regurgitation"),
+    ("cond-hypertension", "Hypertension", "Persistently elevated blood pressure that increases cardiovascular risk"),
+    ("cond-type2-diabetes", "Type 2 Diabetes", "Chronic condition of insulin resistance causing elevated blood glucose"),
+    ("cond-asthma", "Asthma", "Chronic airway inflammation causing wheezing, coughing, and shortness of breath"),
+    ("cond-anxiety", "Anxiety Disorder", "Excessive worry or fear that interferes with daily functioning"),
+    ("cond-uti", "Urinary Tract Infection", "Bacterial infection of the urinary system causing pain and frequent urination"),
+    ("cond-allergic-rhinitis", "Allergic Rhinitis", "Allergy-driven nasal inflammation causing sneezing and congestion"),
+];
+
+/// Holds precomputed embeddings for the bundled condition corpus.
+pub struct ConditionIndex {
+    entries: Vec<(String, String, Vec<f32>)>,
+}
+
+impl ConditionIndex {
+    /// Embeds the bundled condition list once via `ollama`, the same connection used for
+    /// chat, so this honors whatever endpoint/scheme/credentials are currently configured.
+    /// Returns `None` if the embedding model is unavailable so callers can fall back to
+    /// skipping related-condition lookup.
+    pub async fn build(ollama: &OllamaManager) -> Option<Self> {
+        let mut entries = Vec::with_capacity(BUNDLED_CONDITIONS.len());
+
+        for (id, name, description) in BUNDLED_CONDITIONS {
+            let text = format!("{}: {}", name, description);
+            match ollama.embed(EMBEDDING_MODEL, &text).await {
+                Ok(embedding) => entries.push((id.to_string(), name.to_string(), embedding)),
+                Err(e) => {
+                    eprintln!("Could not build condition embedding index: {}", e);
+                    return None;
+                }
+            }
+        }
+
+        Some(Self { entries })
+    }
+
+    /// Returns the top-k conditions above `threshold` cosine similarity to `query`.
+    pub async fn search(
+        &self,
+        query: &str,
+        top_k: usize,
+        threshold: f64,
+        ollama: &OllamaManager,
+    ) -> Vec<RelatedCondition> {
+        let query_embedding = match ollama.embed(EMBEDDING_MODEL, query).await {
+            Ok(v) => v,
+            Err(e) => {
+                eprintln!("Could not embed query for related-condition search: {}", e);
+                return Vec::new();
+            }
+        };
+
+        let mut scored: Vec<RelatedCondition> = self
+            .entries
+            .iter()
+            .map(|(id, name, embedding)| RelatedCondition {
+                id: id.clone(),
+                name: name.clone(),
+                similarity: cosine_similarity(&query_embedding, embedding),
+            })
+            .filter(|c| c.similarity >= threshold)
+            .collect();
+
+        scored.sort_by(|a, b| {
+            b.similarity
+                .partial_cmp(&a.similarity)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        scored.truncate(top_k);
+        scored
+    }
+}
+
+fn cosine_similarity(a: &[f32], b: &[f32]) -> f64 {
+    let dot: f32 = a.iter().zip(b.iter()).map(|(x, y)| x * y).sum();
+    let norm_a: f32 = a.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let norm_b: f32 = b.iter().map(|x| x * x).sum::<f32>().sqrt();
+
+    if norm_a == 0.0 || norm_b == 0.0 {
+        return 0.0;
+    }
+
+    (dot / (norm_a * norm_b)) as f64
+}